@@ -1,155 +1,452 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::Command;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::fs::{create_dir_all, OpenOptions};
-use std::io::Write;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::path::PathBuf;
+use futures_util::StreamExt;
 use serialport::{available_ports, SerialPortType};
 use tauri::{Manager, State};
 use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+use tts::{Gender, Tts};
 
-// Global state for serial connection
-struct SerialState {
-    port: Arc<Mutex<Option<Box<dyn serialport::SerialPort>>>>,
+/// Which transport `StreamState` is currently backed by
+enum ActiveTransport {
+    None,
+    Serial,
+    Tcp,
+    WebSocket,
+}
+
+// General state for the active sensor stream, covering both local serial
+// ports and networked (TCP/WebSocket) connections so the rest of the app
+// can stay transport-agnostic.
+struct StreamState {
+    serial_port: Arc<Mutex<Option<Box<dyn serialport::SerialPort>>>>,
+    tcp_stream: Arc<Mutex<Option<TcpStream>>>,
+    // Unlike the serial/TCP read loops, which poll `is_connected` on a timer,
+    // the WebSocket task blocks on `read.next().await` between messages, so
+    // it needs its handle held onto for `disconnect_serial` to abort directly.
+    ws_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    active: Arc<Mutex<ActiveTransport>>,
     is_connected: Arc<Mutex<bool>>,
 }
 
-/// Text-to-Speech command that uses OS-native TTS engines
-/// 
-/// # Arguments
-/// * `text` - The text to be spoken
-/// * `lang` - Optional language code (e.g., "tr-TR", "en-US")
-/// 
-/// # Platform-specific implementations
-/// - Windows: PowerShell + SAPI (System.Speech.Synthesis)
-/// - macOS: `say` command with voice selection
-/// - Linux: `spd-say` (Speech Dispatcher)
-#[tauri::command]
-fn tts_say(text: String, lang: Option<String>) -> Result<(), String> {
-    // Validate input
-    if text.trim().is_empty() {
-        return Err("Text cannot be empty".to_string());
+// Clients subscribed to the outbound sensor feed via `start_sensor_server`.
+struct SensorServerState {
+    clients: Arc<Mutex<Vec<TcpStream>>>,
+    listening: Arc<Mutex<bool>>,
+}
+
+// Assumed raw ADC range for the flex sensors, used to normalize channels
+// to 0.0-1.0 for recognition until a session has been calibrated.
+const RAW_SENSOR_MIN: f64 = 0.0;
+const RAW_SENSOR_MAX: f64 = 1023.0;
+
+struct GestureTemplate {
+    label: &'static str,
+    vector: [f64; 5],
+}
+
+// Coarse built-in templates (normalized 0.0-1.0 flex per channel: thumb,
+// index, middle, ring, pinky), compared against channels normalized via the
+// active `CalibrationData` (see `normalize_channels`). Placeholder
+// nearest-template classifier until per-user trained templates are available.
+const GESTURE_TEMPLATES: &[GestureTemplate] = &[
+    GestureTemplate { label: "rest", vector: [0.0, 0.0, 0.0, 0.0, 0.0] },
+    GestureTemplate { label: "fist", vector: [1.0, 1.0, 1.0, 1.0, 1.0] },
+    GestureTemplate { label: "point", vector: [1.0, 0.0, 1.0, 1.0, 1.0] },
+    GestureTemplate { label: "thumbs_up", vector: [0.0, 1.0, 1.0, 1.0, 1.0] },
+];
+
+/// Tunables for the streaming gesture recognizer, adjustable at runtime via
+/// `set_recognition_config`.
+#[derive(Clone, Copy)]
+struct RecognitionConfig {
+    window_size: usize,
+    confidence_threshold: f64,
+    rest_threshold: f64,
+    stable_windows_required: u32,
+}
+
+impl Default for RecognitionConfig {
+    fn default() -> Self {
+        RecognitionConfig {
+            window_size: 40,
+            confidence_threshold: 0.75,
+            rest_threshold: 0.05,
+            stable_windows_required: 5,
+        }
     }
+}
 
-    // Sanitize text to prevent command injection
-    let sanitized_text = text.replace("\"", "\\\"").replace("`", "");
+struct RecognitionState {
+    config: Arc<Mutex<RecognitionConfig>>,
+}
 
-    let lang_code = lang.unwrap_or_else(|| "en-US".to_string());
+#[derive(Clone, Serialize)]
+struct GesturePartialEvent {
+    label: String,
+    confidence: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct GestureFinalEvent {
+    label: String,
+    confidence: f64,
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        tts_say_windows(&sanitized_text, &lang_code)
+/// Normalize raw channel readings into the 0.0-1.0 range the classifier and
+/// templates operate on, using the per-channel calibrated baseline/maxbend
+/// when a channel's calibrated range is usable, and falling back to the
+/// fixed raw ADC range otherwise (e.g. before the user has calibrated)
+fn normalize_channels(raw: [i32; 5], calibration: &CalibrationData) -> [f64; 5] {
+    let mut out = [0.0; 5];
+    for i in 0..5 {
+        let baseline = calibration.baseline[i] as f64;
+        let maxbend = calibration.maxbend[i] as f64;
+        let range = maxbend - baseline;
+        let v = if range > 0.0 {
+            (raw[i] as f64 - baseline) / range
+        } else {
+            (raw[i] as f64 - RAW_SENSOR_MIN) / (RAW_SENSOR_MAX - RAW_SENSOR_MIN)
+        };
+        out[i] = v.clamp(0.0, 1.0);
     }
+    out
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        tts_say_macos(&sanitized_text, &lang_code)
+fn average_window(window: &VecDeque<[f64; 5]>) -> [f64; 5] {
+    let mut sum = [0.0; 5];
+    for sample in window {
+        for i in 0..5 {
+            sum[i] += sample[i];
+        }
     }
+    let n = window.len().max(1) as f64;
+    for v in sum.iter_mut() {
+        *v /= n;
+    }
+    sum
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        tts_say_linux(&sanitized_text, &lang_code)
+/// Average per-sample channel movement across the window; used to detect
+/// when the hand has returned to rest
+fn motion_energy(window: &VecDeque<[f64; 5]>) -> f64 {
+    if window.len() < 2 {
+        return 0.0;
+    }
+    let mut total = 0.0;
+    let mut steps = 0;
+    let mut prev: Option<&[f64; 5]> = None;
+    for sample in window {
+        if let Some(p) = prev {
+            for i in 0..5 {
+                total += (sample[i] - p[i]).abs();
+            }
+            steps += 1;
+        }
+        prev = Some(sample);
+    }
+    if steps == 0 {
+        0.0
+    } else {
+        total / (steps as f64 * 5.0)
     }
 }
 
-#[cfg(target_os = "windows")]
-fn tts_say_windows(text: &str, lang: &str) -> Result<(), String> {
-    // Map language codes to Windows SAPI culture codes
-    let culture_code = match lang {
-        "tr-TR" | "tr" => "tr-TR",
-        "en-US" | "en" => "en-US",
-        "en-GB" => "en-GB",
-        _ => "en-US", // Default fallback
-    };
+/// Nearest-template classification over the window average; returns the
+/// closest label and a confidence in 0.0-1.0
+fn classify_window(window: &VecDeque<[f64; 5]>) -> (String, f64) {
+    let avg = average_window(window);
+    // Max possible Euclidean distance between two points in the 5-dim unit cube.
+    const MAX_DISTANCE: f64 = 2.23606797749979; // sqrt(5.0)
 
-    // PowerShell script to use SAPI for TTS
-    let ps_script = format!(
-        r#"
-        Add-Type -AssemblyName System.Speech
-        $synth = New-Object System.Speech.Synthesis.SpeechSynthesizer
-        
-        # Try to select a voice for the specified culture
-        $voice = $synth.GetInstalledVoices() | Where-Object {{
-            $_.VoiceInfo.Culture.Name -eq '{}'
-        }} | Select-Object -First 1
-        
-        if ($voice) {{
-            $synth.SelectVoice($voice.VoiceInfo.Name)
-        }}
-        
-        $synth.Speak('{}')
-        $synth.Dispose()
-        "#,
-        culture_code,
-        text.replace("'", "''") // Escape single quotes for PowerShell
-    );
+    let mut best_label = "unknown";
+    let mut best_distance = f64::MAX;
+    for template in GESTURE_TEMPLATES {
+        let distance: f64 = (0..5)
+            .map(|i| (avg[i] - template.vector[i]).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if distance < best_distance {
+            best_distance = distance;
+            best_label = template.label;
+        }
+    }
 
-    let output = Command::new("powershell")
-        .args(["-NoProfile", "-NonInteractive", "-Command", &ps_script])
-        .output()
-        .map_err(|e| format!("Failed to execute PowerShell: {}", e))?;
+    let confidence = (1.0 - (best_distance / MAX_DISTANCE)).clamp(0.0, 1.0);
+    (best_label.to_string(), confidence)
+}
+
+/// Per-connection recognizer state, carried across read-loop iterations
+/// regardless of which transport is feeding it samples.
+struct GestureRecognizer {
+    window: VecDeque<[f64; 5]>,
+    stable_label: Option<String>,
+    stable_count: u32,
+    last_finalized: Option<String>,
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("PowerShell TTS failed: {}", stderr));
+impl GestureRecognizer {
+    fn new() -> Self {
+        GestureRecognizer {
+            window: VecDeque::new(),
+            stable_label: None,
+            stable_count: 0,
+            last_finalized: None,
+        }
     }
 
-    Ok(())
-}
+    /// Advance the stability/dedup state machine for one classified window
+    /// and decide whether it finalizes a gesture. Kept free of `AppHandle` so
+    /// it can be unit tested directly.
+    fn step(&mut self, label: &str, confidence: f64, energy: f64, config: RecognitionConfig) -> Option<String> {
+        if confidence >= config.confidence_threshold && label != "rest" {
+            if self.stable_label.as_deref() == Some(label) {
+                self.stable_count += 1;
+            } else {
+                self.stable_label = Some(label.to_string());
+                self.stable_count = 1;
+            }
+        } else {
+            self.stable_label = None;
+            self.stable_count = 0;
+        }
 
-#[cfg(target_os = "macos")]
-fn tts_say_macos(text: &str, lang: &str) -> Result<(), String> {
-    // Map language codes to macOS voice names
-    let voice = match lang {
-        "tr-TR" | "tr" => "Yelda", // Turkish voice (if installed)
-        "en-US" | "en" => "Samantha", // US English voice
-        "en-GB" => "Daniel", // British English voice
-        _ => "Samantha", // Default fallback
-    };
+        let at_rest = energy < config.rest_threshold;
+        let should_finalize = self.stable_label.is_some()
+            && (self.stable_count >= config.stable_windows_required || at_rest);
+
+        let mut finalized = None;
+        if should_finalize {
+            if let Some(final_label) = self.stable_label.take() {
+                if self.last_finalized.as_deref() != Some(final_label.as_str()) {
+                    self.last_finalized = Some(final_label.clone());
+                    finalized = Some(final_label);
+                }
+            }
+            self.stable_count = 0;
+        }
+
+        // Only re-arm the dedup once the hand actually returns to rest, not on
+        // every low-motion window -- a pose held steady also reports near-zero
+        // `motion_energy`, and clearing this on `at_rest` alone caused a held
+        // gesture to re-finalize (and re-speak) on every window forever.
+        if label == "rest" {
+            self.last_finalized = None;
+        }
+
+        finalized
+    }
+
+    fn process_sample(&mut self, raw: [i32; 5], config: RecognitionConfig, app_handle: &tauri::AppHandle) {
+        let calibration = app_handle.state::<CalibrationState>().data.lock().unwrap().clone();
+        let normalized = normalize_channels(raw, &calibration);
+        self.window.push_back(normalized);
+        while self.window.len() > config.window_size {
+            self.window.pop_front();
+        }
 
-    let output = Command::new("say")
-        .args(["-v", voice, text])
-        .output()
-        .map_err(|e| format!("Failed to execute 'say' command: {}", e))?;
+        if self.window.len() != config.window_size {
+            return;
+        }
+
+        let (label, confidence) = classify_window(&self.window);
+        let energy = motion_energy(&self.window);
+
+        let _ = app_handle.emit_all(
+            "gesture-partial",
+            GesturePartialEvent { label: label.clone(), confidence },
+        );
 
-    if !output.status.success() {
-        // If the specified voice doesn't exist, try without voice parameter
-        let fallback_output = Command::new("say")
-            .arg(text)
-            .output()
-            .map_err(|e| format!("Failed to execute 'say' command (fallback): {}", e))?;
+        if let Some(final_label) = self.step(&label, confidence, energy, config) {
+            let _ = app_handle.emit_all(
+                "gesture-final",
+                GestureFinalEvent { label: final_label.clone(), confidence },
+            );
 
-        if !fallback_output.status.success() {
-            let stderr = String::from_utf8_lossy(&fallback_output.stderr);
-            return Err(format!("macOS TTS failed: {}", stderr));
+            // Speak the recognized gesture, interrupting any stale utterance
+            let tts_state = app_handle.state::<TtsState>();
+            let mut engine = tts_state.engine.lock().unwrap();
+            let _ = engine.speak(&final_label, true);
         }
     }
+}
+
+/// Forward a raw sensor/gesture line to every client subscribed via
+/// `start_sensor_server`, dropping any that error out (disconnected)
+fn broadcast_to_sensor_server(line: &str, app_handle: &tauri::AppHandle) {
+    if let Some(server) = app_handle.try_state::<SensorServerState>() {
+        let mut payload = line.as_bytes().to_vec();
+        payload.push(b'\n');
+
+        let mut clients = server.clients.lock().unwrap();
+        clients.retain_mut(|client| client.write_all(&payload).is_ok());
+    }
+}
+
+/// Parse one CSV sensor line, emit it to the frontend and any subscribed
+/// network clients, and feed it through the gesture recognizer. Shared by
+/// every transport (serial, TCP, WebSocket) so they stay behaviorally
+/// identical.
+fn handle_sensor_line(line: &str, recognizer: &mut GestureRecognizer, app_handle: &tauri::AppHandle) {
+    let parts: Vec<&str> = line.split(',').collect();
+    if parts.len() != 6 {
+        return;
+    }
+
+    let _ = app_handle.emit_all("sensor-data", line.to_string());
+    broadcast_to_sensor_server(line, app_handle);
 
+    if let (Ok(ch0), Ok(ch1), Ok(ch2), Ok(ch3), Ok(ch4)) = (
+        parts[1].parse::<i32>(),
+        parts[2].parse::<i32>(),
+        parts[3].parse::<i32>(),
+        parts[4].parse::<i32>(),
+        parts[5].parse::<i32>(),
+    ) {
+        let config = *app_handle.state::<RecognitionState>().config.lock().unwrap();
+        recognizer.process_sample([ch0, ch1, ch2, ch3, ch4], config, app_handle);
+        feed_calibration_capture([ch0, ch1, ch2, ch3, ch4], app_handle);
+    }
+}
+
+/// Tune the streaming gesture recognizer. Any argument left `None` keeps its
+/// current value.
+#[tauri::command]
+fn set_recognition_config(
+    window_size: Option<usize>,
+    confidence_threshold: Option<f64>,
+    rest_threshold: Option<f64>,
+    stable_windows_required: Option<u32>,
+    state: State<'_, RecognitionState>,
+) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap();
+    if let Some(v) = window_size {
+        config.window_size = v.max(1);
+    }
+    if let Some(v) = confidence_threshold {
+        config.confidence_threshold = v.clamp(0.0, 1.0);
+    }
+    if let Some(v) = rest_threshold {
+        config.rest_threshold = v.max(0.0);
+    }
+    if let Some(v) = stable_windows_required {
+        config.stable_windows_required = v.max(1);
+    }
     Ok(())
 }
 
-#[cfg(target_os = "linux")]
-fn tts_say_linux(text: &str, lang: &str) -> Result<(), String> {
-    // Map language codes to Speech Dispatcher language codes
-    let lang_code = match lang {
-        "tr-TR" | "tr" => "tr",
-        "en-US" | "en" | "en-GB" => "en",
-        _ => "en", // Default fallback
-    };
+// Persistent speech synthesizer shared across `tts_say` calls, so speaking
+// no longer spawns an OS process per utterance.
+struct TtsState {
+    engine: Arc<Mutex<Tts>>,
+}
 
-    let output = Command::new("spd-say")
-        .args(["-l", lang_code, text])
-        .output()
-        .map_err(|e| format!("Failed to execute 'spd-say': {}. Make sure speech-dispatcher is installed.", e))?;
+#[derive(Serialize)]
+struct VoiceInfo {
+    id: String,
+    name: String,
+    language: String,
+    gender: Option<String>,
+}
+
+/// List the voices installed on the current speech engine
+#[tauri::command]
+fn list_voices(state: State<'_, TtsState>) -> Result<Vec<VoiceInfo>, String> {
+    let engine = state.engine.lock().unwrap();
+    let voices = engine
+        .voices()
+        .map_err(|e| format!("Failed to list voices: {}", e))?;
+
+    Ok(voices
+        .into_iter()
+        .map(|v| VoiceInfo {
+            id: v.id(),
+            name: v.name(),
+            language: v.language().to_string(),
+            gender: v.gender().map(|g| match g {
+                Gender::Male => "male".to_string(),
+                Gender::Female => "female".to_string(),
+            }),
+        })
+        .collect())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Linux TTS failed: {}", stderr));
+/// Text-to-Speech command backed by a persistent cross-platform synthesizer
+///
+/// # Arguments
+/// * `text` - The text to be spoken
+/// * `voice_id` - Optional id (from `list_voices`) of the voice to speak with
+/// * `rate` - Optional speech rate, engine-defined range (e.g. 0-100 on some backends)
+/// * `pitch` - Optional pitch adjustment
+/// * `volume` - Optional volume, 0.0-1.0
+/// * `interrupt` - If true (the default), flushes any utterance still in progress before
+///   speaking; sign-language output should not queue behind a stale gesture
+#[tauri::command]
+fn tts_say(
+    text: String,
+    voice_id: Option<String>,
+    rate: Option<f32>,
+    pitch: Option<f32>,
+    volume: Option<f32>,
+    interrupt: Option<bool>,
+    state: State<'_, TtsState>,
+) -> Result<(), String> {
+    // Validate input
+    if text.trim().is_empty() {
+        return Err("Text cannot be empty".to_string());
+    }
+
+    let mut engine = state.engine.lock().unwrap();
+
+    if let Some(id) = voice_id {
+        let voices = engine
+            .voices()
+            .map_err(|e| format!("Failed to list voices: {}", e))?;
+        if let Some(voice) = voices.into_iter().find(|v| v.id() == id) {
+            engine
+                .set_voice(&voice)
+                .map_err(|e| format!("Failed to set voice: {}", e))?;
+        }
+    }
+    if let Some(rate) = rate {
+        engine
+            .set_rate(rate)
+            .map_err(|e| format!("Failed to set rate: {}", e))?;
+    }
+    if let Some(pitch) = pitch {
+        engine
+            .set_pitch(pitch)
+            .map_err(|e| format!("Failed to set pitch: {}", e))?;
+    }
+    if let Some(volume) = volume {
+        engine
+            .set_volume(volume)
+            .map_err(|e| format!("Failed to set volume: {}", e))?;
     }
 
+    let interrupt = interrupt.unwrap_or(true);
+    engine
+        .speak(&text, interrupt)
+        .map_err(|e| format!("Failed to speak: {}", e))?;
+
+    Ok(())
+}
+
+/// Stop any speech currently in progress
+#[tauri::command]
+fn tts_stop(state: State<'_, TtsState>) -> Result<(), String> {
+    let mut engine = state.engine.lock().unwrap();
+    engine
+        .stop()
+        .map_err(|e| format!("Failed to stop speech: {}", e))?;
     Ok(())
 }
 
@@ -177,7 +474,7 @@ fn list_ports() -> Result<Vec<String>, String> {
 async fn connect_serial(
     port_name: String,
     baud_rate: u32,
-    state: State<'_, SerialState>,
+    state: State<'_, StreamState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
     // Check if already connected
@@ -194,22 +491,18 @@ async fn connect_serial(
         .map_err(|e| format!("Failed to open port {}: {}", port_name, e))?;
 
     // Store the port in state
-    let mut port_lock = state.port.lock().unwrap();
-    *port_lock = Some(port);
-    drop(port_lock);
-
-    // Mark as connected
-    let mut is_connected = state.is_connected.lock().unwrap();
-    *is_connected = true;
-    drop(is_connected);
+    *state.serial_port.lock().unwrap() = Some(port);
+    *state.active.lock().unwrap() = ActiveTransport::Serial;
+    *state.is_connected.lock().unwrap() = true;
 
     // Spawn a task to read from the serial port
-    let port_arc = state.port.clone();
+    let port_arc = state.serial_port.clone();
     let is_connected_arc = state.is_connected.clone();
-    
+
     tokio::spawn(async move {
         let mut buffer = String::new();
-        
+        let mut recognizer = GestureRecognizer::new();
+
         loop {
             // Check if still connected
             {
@@ -229,19 +522,14 @@ async fn connect_serial(
                             if bytes_read > 0 {
                                 let data = String::from_utf8_lossy(&serial_buf[..bytes_read]);
                                 buffer.push_str(&data);
-                                
+
                                 // Process complete lines
                                 while let Some(newline_pos) = buffer.find('\n') {
                                     let line = buffer[..newline_pos].trim().to_string();
                                     buffer = buffer[newline_pos + 1..].to_string();
-                                    
+
                                     if !line.is_empty() {
-                                        // Parse CSV line: timestamp,ch0,ch1,ch2,ch3,ch4
-                                        let parts: Vec<&str> = line.split(',').collect();
-                                        if parts.len() == 6 {
-                                            // Emit event to frontend
-                                            let _ = app_handle.emit_all("sensor-data", line);
-                                        }
+                                        handle_sensor_line(&line, &mut recognizer, &app_handle);
                                     }
                                 }
                             }
@@ -266,27 +554,212 @@ async fn connect_serial(
     Ok(())
 }
 
-/// Disconnect from the serial port
+/// Connect to the glove (or a relay) over the network instead of USB serial.
+/// `mode` is either `"tcp"` or `"websocket"`; both feed the same CSV
+/// line-parsing and `sensor-data` emission path as `connect_serial`.
 #[tauri::command]
-fn disconnect_serial(state: State<'_, SerialState>) -> Result<(), String> {
-    let mut is_connected = state.is_connected.lock().unwrap();
-    *is_connected = false;
+async fn connect_network(
+    addr: String,
+    mode: String,
+    state: State<'_, StreamState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let is_connected = state.is_connected.lock().unwrap();
+    if *is_connected {
+        return Err("Already connected to a transport".to_string());
+    }
     drop(is_connected);
 
-    let mut port_lock = state.port.lock().unwrap();
-    *port_lock = None;
-    drop(port_lock);
+    match mode.as_str() {
+        "tcp" => {
+            let stream = TcpStream::connect(&addr)
+                .map_err(|e| format!("Failed to connect to {}: {}", addr, e))?;
+            stream
+                .set_read_timeout(Some(Duration::from_millis(100)))
+                .map_err(|e| format!("Failed to configure stream: {}", e))?;
+
+            *state.tcp_stream.lock().unwrap() = Some(stream);
+            *state.active.lock().unwrap() = ActiveTransport::Tcp;
+            *state.is_connected.lock().unwrap() = true;
+
+            let tcp_arc = state.tcp_stream.clone();
+            let is_connected_arc = state.is_connected.clone();
+
+            tokio::spawn(async move {
+                let mut buffer = String::new();
+                let mut recognizer = GestureRecognizer::new();
+
+                loop {
+                    {
+                        let is_connected = is_connected_arc.lock().unwrap();
+                        if !*is_connected {
+                            break;
+                        }
+                    }
+
+                    {
+                        let mut stream_lock = tcp_arc.lock().unwrap();
+                        if let Some(stream) = stream_lock.as_mut() {
+                            let mut read_buf = vec![0u8; 1024];
+                            match stream.read(&mut read_buf) {
+                                Ok(bytes_read) if bytes_read > 0 => {
+                                    let data = String::from_utf8_lossy(&read_buf[..bytes_read]);
+                                    buffer.push_str(&data);
+
+                                    while let Some(newline_pos) = buffer.find('\n') {
+                                        let line = buffer[..newline_pos].trim().to_string();
+                                        buffer = buffer[newline_pos + 1..].to_string();
+
+                                        if !line.is_empty() {
+                                            handle_sensor_line(&line, &mut recognizer, &app_handle);
+                                        }
+                                    }
+                                }
+                                Ok(_) => {}
+                                Err(ref e)
+                                    if e.kind() == std::io::ErrorKind::WouldBlock
+                                        || e.kind() == std::io::ErrorKind::TimedOut => {}
+                                Err(e) => {
+                                    eprintln!("Error reading from network stream: {}", e);
+                                    let _ = app_handle.emit_all("serial-error", format!("Read error: {}", e));
+                                    break;
+                                }
+                            }
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            });
+
+            Ok(())
+        }
+        "websocket" => {
+            let (ws_stream, _response) = tokio_tungstenite::connect_async(&addr)
+                .await
+                .map_err(|e| format!("Failed to connect WebSocket to {}: {}", addr, e))?;
+
+            *state.active.lock().unwrap() = ActiveTransport::WebSocket;
+            *state.is_connected.lock().unwrap() = true;
+
+            let is_connected_arc = state.is_connected.clone();
+
+            let handle = tokio::spawn(async move {
+                let mut recognizer = GestureRecognizer::new();
+                let (_write, mut read) = ws_stream.split();
+
+                while let Some(message) = read.next().await {
+                    {
+                        let is_connected = is_connected_arc.lock().unwrap();
+                        if !*is_connected {
+                            break;
+                        }
+                    }
+
+                    match message {
+                        Ok(Message::Text(text)) => {
+                            for line in text.lines() {
+                                let line = line.trim();
+                                if !line.is_empty() {
+                                    handle_sensor_line(line, &mut recognizer, &app_handle);
+                                }
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            eprintln!("Error reading from WebSocket: {}", e);
+                            let _ = app_handle.emit_all("serial-error", format!("WebSocket error: {}", e));
+                            break;
+                        }
+                    }
+                }
+            });
+
+            *state.ws_task.lock().unwrap() = Some(handle);
+
+            Ok(())
+        }
+        other => Err(format!("Unsupported network mode: {}", other)),
+    }
+}
+
+/// Disconnect the active transport, whichever one it is
+#[tauri::command]
+fn disconnect_serial(state: State<'_, StreamState>) -> Result<(), String> {
+    *state.is_connected.lock().unwrap() = false;
+    *state.serial_port.lock().unwrap() = None;
+    *state.tcp_stream.lock().unwrap() = None;
+
+    // The serial/TCP read loops notice `is_connected` on their next iteration
+    // (at most one 100ms read timeout plus a 10ms sleep), but the
+    // WebSocket task blocks on `read.next().await` and may not see it until
+    // (or unless) another message arrives -- abort it directly instead.
+    if matches!(*state.active.lock().unwrap(), ActiveTransport::WebSocket) {
+        if let Some(handle) = state.ws_task.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    *state.active.lock().unwrap() = ActiveTransport::None;
 
     Ok(())
 }
 
-/// Check if currently connected
+/// Check if a transport (serial or network) is currently connected
 #[tauri::command]
-fn is_serial_connected(state: State<'_, SerialState>) -> Result<bool, String> {
+fn is_serial_connected(state: State<'_, StreamState>) -> Result<bool, String> {
     let is_connected = state.is_connected.lock().unwrap();
     Ok(*is_connected)
 }
 
+/// Start a TCP server that forwards the incoming sensor/gesture stream to
+/// connected clients, e.g. a phone or second machine for remote captioning.
+#[tauri::command]
+async fn start_sensor_server(port: u16, state: State<'_, SensorServerState>) -> Result<(), String> {
+    let mut listening = state.listening.lock().unwrap();
+    if *listening {
+        return Err("Sensor server is already running".to_string());
+    }
+
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .map_err(|e| format!("Failed to bind sensor server to port {}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure sensor server listener: {}", e))?;
+
+    *listening = true;
+    drop(listening);
+
+    let clients = state.clients.clone();
+    let listening_arc = state.listening.clone();
+
+    tokio::spawn(async move {
+        loop {
+            {
+                let listening = listening_arc.lock().unwrap();
+                if !*listening {
+                    break;
+                }
+            }
+
+            match listener.accept() {
+                Ok((stream, _addr)) => {
+                    let _ = stream.set_nodelay(true);
+                    clients.lock().unwrap().push(stream);
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => {
+                    eprintln!("Error accepting sensor server client: {}", e);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+
+    Ok(())
+}
+
 #[derive(Deserialize)]
 struct SensorSample {
     timestamp: i64,
@@ -297,12 +770,334 @@ struct SensorSample {
     ch4: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 struct CalibrationData {
     baseline: Vec<i32>,
     maxbend: Vec<i32>,
 }
 
+impl Default for CalibrationData {
+    fn default() -> Self {
+        CalibrationData {
+            baseline: vec![0; 5],
+            maxbend: vec![RAW_SENSOR_MAX as i32; 5],
+        }
+    }
+}
+
+// Friendly names for the 5 flex channels, matching the CH0-CH4 mapping written
+// into the `sensor_map_ref` column of `save_recording`'s CSV output.
+const CHANNEL_NAMES: [&str; 5] = ["thumb", "index", "middle", "ring", "pinky"];
+
+// A calibration channel narrower than this many raw ADC units is treated as
+// unreliable, mirroring (and making explicit) the `range > 0.0` guard that
+// `save_recording` otherwise applies silently.
+const MIN_CALIBRATION_RANGE: f64 = 10.0;
+
+// Number of samples kept in the rolling window used to derive the baseline
+// median; "maxbend" instead tracks a running max over the whole capture.
+const CALIBRATION_BASELINE_WINDOW: usize = 100;
+
+/// Which quantity a `CalibrationCapture` is currently deriving from the live
+/// `sensor-data` stream
+#[derive(Clone, Copy)]
+enum CalibrationMode {
+    Baseline,
+    Maxbend,
+}
+
+impl CalibrationMode {
+    fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "baseline" => Ok(CalibrationMode::Baseline),
+            "maxbend" => Ok(CalibrationMode::Maxbend),
+            other => Err(format!("Unsupported calibration mode: {}", other)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CalibrationMode::Baseline => "baseline",
+            CalibrationMode::Maxbend => "maxbend",
+        }
+    }
+}
+
+// In-progress calibration capture, fed one sample at a time from
+// `handle_sensor_line` while a calibration is active.
+struct CalibrationCapture {
+    mode: CalibrationMode,
+    baseline_window: VecDeque<[i32; 5]>,
+    maxbend_running_max: [i32; 5],
+    samples_seen: u32,
+}
+
+impl CalibrationCapture {
+    fn new(mode: CalibrationMode) -> Self {
+        CalibrationCapture {
+            mode,
+            baseline_window: VecDeque::new(),
+            maxbend_running_max: [i32::MIN; 5],
+            samples_seen: 0,
+        }
+    }
+}
+
+// Live calibration subsystem: observes the active sensor stream to build
+// `CalibrationData` in place of requiring it fully-formed up front, and keeps
+// the last finished result around so it can be reloaded across sessions.
+struct CalibrationState {
+    capture: Arc<Mutex<Option<CalibrationCapture>>>,
+    data: Arc<Mutex<CalibrationData>>,
+}
+
+#[derive(Clone, Serialize)]
+struct CalibrationProgressEvent {
+    mode: &'static str,
+    samples_collected: u32,
+    // Per-channel progress toward a usable calibration: window fill for
+    // baseline, fraction of the sensor's raw range reached for maxbend.
+    channel_coverage: [f64; 5],
+}
+
+#[derive(Clone, Serialize)]
+struct CalibrationResult {
+    calibration: CalibrationData,
+    warnings: Vec<String>,
+}
+
+fn channel_median(window: &VecDeque<[i32; 5]>, channel: usize) -> i32 {
+    let mut values: Vec<i32> = window.iter().map(|sample| sample[channel]).collect();
+    values.sort();
+    values[values.len() / 2]
+}
+
+/// Feed one raw sample to the active calibration capture (if any) and emit a
+/// `calibration-progress` event so the UI can guide the user
+fn feed_calibration_capture(raw: [i32; 5], app_handle: &tauri::AppHandle) {
+    let calibration_state = app_handle.state::<CalibrationState>();
+    let mut capture_guard = calibration_state.capture.lock().unwrap();
+    let capture = match capture_guard.as_mut() {
+        Some(capture) => capture,
+        None => return,
+    };
+
+    capture.samples_seen += 1;
+    let mut channel_coverage = [0.0; 5];
+
+    match capture.mode {
+        CalibrationMode::Baseline => {
+            capture.baseline_window.push_back(raw);
+            while capture.baseline_window.len() > CALIBRATION_BASELINE_WINDOW {
+                capture.baseline_window.pop_front();
+            }
+            let coverage = capture.baseline_window.len() as f64 / CALIBRATION_BASELINE_WINDOW as f64;
+            channel_coverage = [coverage; 5];
+        }
+        CalibrationMode::Maxbend => {
+            for i in 0..5 {
+                capture.maxbend_running_max[i] = capture.maxbend_running_max[i].max(raw[i]);
+                channel_coverage[i] = (capture.maxbend_running_max[i] as f64 / RAW_SENSOR_MAX).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    let _ = app_handle.emit_all(
+        "calibration-progress",
+        CalibrationProgressEvent {
+            mode: capture.mode.as_str(),
+            samples_collected: capture.samples_seen,
+            channel_coverage,
+        },
+    );
+}
+
+fn calibration_file_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("Failed to get app data directory")?;
+    Ok(app_dir.join("calibration.json"))
+}
+
+/// Reload a previously persisted calibration, if any, so it doesn't need to
+/// be rebuilt from scratch every launch
+fn load_calibration_from_disk(app_handle: &tauri::AppHandle) -> Option<CalibrationData> {
+    let path = calibration_file_path(app_handle).ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_calibration_to_disk(app_handle: &tauri::AppHandle, data: &CalibrationData) -> Result<(), String> {
+    let path = calibration_file_path(app_handle)?;
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize calibration: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write calibration file: {}", e))
+}
+
+/// Begin a live calibration capture observing the active `sensor-data`
+/// stream. `mode` is either `"baseline"` (relaxed hand, median per channel)
+/// or `"maxbend"` (flex-all-fingers gesture, max per channel).
+#[tauri::command]
+fn start_calibration(mode: String, state: State<'_, CalibrationState>) -> Result<(), String> {
+    let parsed_mode = CalibrationMode::parse(&mode)?;
+
+    let mut capture = state.capture.lock().unwrap();
+    if capture.is_some() {
+        return Err("Calibration is already in progress".to_string());
+    }
+    *capture = Some(CalibrationCapture::new(parsed_mode));
+    Ok(())
+}
+
+/// Stop the active calibration capture, merge its result into the stored
+/// `CalibrationData`, persist it to disk, and report any channels whose
+/// range is too small to be usable
+#[tauri::command]
+fn finish_calibration(
+    state: State<'_, CalibrationState>,
+    app_handle: tauri::AppHandle,
+) -> Result<CalibrationResult, String> {
+    let capture = state
+        .capture
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No calibration in progress")?;
+
+    if capture.samples_seen == 0 {
+        return Err("No samples were collected during calibration".to_string());
+    }
+
+    let mut data = state.data.lock().unwrap();
+    match capture.mode {
+        CalibrationMode::Baseline => {
+            for i in 0..5 {
+                data.baseline[i] = channel_median(&capture.baseline_window, i);
+            }
+        }
+        CalibrationMode::Maxbend => {
+            for i in 0..5 {
+                data.maxbend[i] = capture.maxbend_running_max[i];
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for i in 0..5 {
+        let range = data.maxbend[i] as f64 - data.baseline[i] as f64;
+        if range < MIN_CALIBRATION_RANGE {
+            warnings.push(format!(
+                "{} channel range is too small ({:.0}); recalibrate before recording",
+                CHANNEL_NAMES[i], range
+            ));
+        }
+    }
+
+    save_calibration_to_disk(&app_handle, &data)?;
+
+    Ok(CalibrationResult {
+        calibration: data.clone(),
+        warnings,
+    })
+}
+
+// Number of leading FFT magnitude bins included per channel in the features sidecar
+const FEATURE_BIN_COUNT: usize = 5;
+
+struct ChannelSpectralFeatures {
+    spectral_centroid_hz: f64,
+    dominant_frequency_hz: f64,
+    band_energy: f64,
+    magnitude_bins: Vec<f64>,
+}
+
+fn next_pow2(n: usize) -> usize {
+    let mut p = 1;
+    while p < n {
+        p *= 2;
+    }
+    p
+}
+
+/// Estimate the recording's sample rate from the median delta between
+/// consecutive timestamps, rather than assuming a fixed rate
+fn estimate_sample_rate_hz(timestamps_ms: &[i64]) -> f64 {
+    if timestamps_ms.len() < 2 {
+        return 1.0;
+    }
+
+    let mut deltas: Vec<i64> = timestamps_ms.windows(2).map(|w| w[1] - w[0]).collect();
+    deltas.sort();
+    let median_ms = deltas[deltas.len() / 2] as f64;
+
+    if median_ms <= 0.0 {
+        1.0
+    } else {
+        1000.0 / median_ms
+    }
+}
+
+fn hann_window(len: usize) -> Vec<f64> {
+    if len <= 1 {
+        return vec![1.0; len];
+    }
+    (0..len)
+        .map(|i| 0.5 - 0.5 * ((2.0 * std::f64::consts::PI * i as f64) / (len as f64 - 1.0)).cos())
+        .collect()
+}
+
+/// Apply a Hann window and run a real-to-complex FFT over one channel's
+/// normalized time series, deriving compact spectral descriptors. Windows are
+/// zero-padded up to the next power of two, and short ones to at least enough
+/// points to yield `FEATURE_BIN_COUNT` bins.
+fn compute_channel_features(series: &[f64], sample_rate_hz: f64) -> ChannelSpectralFeatures {
+    let window = hann_window(series.len());
+    let mut windowed: Vec<f64> = series.iter().zip(window.iter()).map(|(v, w)| v * w).collect();
+
+    let fft_len = next_pow2(windowed.len().max(2 * (FEATURE_BIN_COUNT - 1)));
+    windowed.resize(fft_len, 0.0);
+
+    let mut planner = realfft::RealFftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(fft_len);
+    let mut spectrum = fft.make_output_vec(); // N/2+1 complex bins for an N-point real input
+    fft.process(&mut windowed, &mut spectrum)
+        .expect("FFT of a correctly-sized, zero-padded buffer cannot fail");
+
+    let magnitudes: Vec<f64> = spectrum.iter().map(|c| (c.re * c.re + c.im * c.im).sqrt()).collect();
+    let bin_hz = sample_rate_hz / fft_len as f64;
+
+    let band_energy: f64 = magnitudes.iter().map(|m| m * m).sum();
+    let magnitude_sum: f64 = magnitudes.iter().sum();
+    let spectral_centroid_hz = if magnitude_sum > 0.0 {
+        magnitudes
+            .iter()
+            .enumerate()
+            .map(|(bin, m)| bin as f64 * bin_hz * m)
+            .sum::<f64>()
+            / magnitude_sum
+    } else {
+        0.0
+    };
+
+    let dominant_bin = magnitudes
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .map(|(bin, _)| bin)
+        .unwrap_or(0);
+
+    ChannelSpectralFeatures {
+        spectral_centroid_hz,
+        dominant_frequency_hz: dominant_bin as f64 * bin_hz,
+        band_energy,
+        magnitude_bins: magnitudes.into_iter().take(FEATURE_BIN_COUNT).collect(),
+    }
+}
+
 /// Save recorded sensor data to CSV
 #[tauri::command]
 fn save_recording(
@@ -311,13 +1106,14 @@ fn save_recording(
     user_id: String,
     session_id: String,
     calibration: CalibrationData,
+    compute_features: bool,
     app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
     // Create data directory in app data folder
     let app_dir = app_handle.path_resolver()
         .app_data_dir()
         .ok_or("Failed to get app data directory")?;
-    
+
     let data_dir = app_dir.join("recordings");
     create_dir_all(&data_dir)
         .map_err(|e| format!("Failed to create data directory: {}", e))?;
@@ -342,12 +1138,15 @@ fn save_recording(
     writeln!(file, "timestamp_ms,user_id,session_id,class_label,ch0_raw,ch1_raw,ch2_raw,ch3_raw,ch4_raw,ch0_norm,ch1_norm,ch2_norm,ch3_norm,ch4_norm,baseline_ch0,baseline_ch1,baseline_ch2,baseline_ch3,baseline_ch4,maxbend_ch0,maxbend_ch1,maxbend_ch2,maxbend_ch3,maxbend_ch4,glove_fit,sensor_map_ref,notes")
         .map_err(|e| format!("Failed to write header: {}", e))?;
 
-    // Write data rows
-    for sample in samples {
+    // Write data rows, keeping the normalized series around in case spectral features are requested
+    let mut timestamps: Vec<i64> = Vec::with_capacity(samples.len());
+    let mut normalized_series: Vec<[f64; 5]> = Vec::with_capacity(samples.len());
+
+    for sample in &samples {
         // Calculate normalized values: (raw - baseline) / (maxbend - baseline)
         let mut norm = vec![0.0; 5];
         let raw = vec![sample.ch0, sample.ch1, sample.ch2, sample.ch3, sample.ch4];
-        
+
         for i in 0..5 {
             let baseline = calibration.baseline[i] as f64;
             let maxbend = calibration.maxbend[i] as f64;
@@ -370,6 +1169,41 @@ fn save_recording(
             calibration.baseline[0], calibration.baseline[1], calibration.baseline[2], calibration.baseline[3], calibration.baseline[4],
             calibration.maxbend[0], calibration.maxbend[1], calibration.maxbend[2], calibration.maxbend[3], calibration.maxbend[4]
         ).map_err(|e| format!("Failed to write data: {}", e))?;
+
+        timestamps.push(sample.timestamp);
+        normalized_series.push([norm[0], norm[1], norm[2], norm[3], norm[4]]);
+    }
+
+    if compute_features && !normalized_series.is_empty() {
+        let sample_rate_hz = estimate_sample_rate_hz(&timestamps);
+        let features_filename = format!("{}_{}_{}_{}_features.csv", user_id, session_id, gesture, timestamp);
+        let features_filepath = data_dir.join(&features_filename);
+
+        let mut features_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&features_filepath)
+            .map_err(|e| format!("Failed to create features file: {}", e))?;
+
+        let bin_headers: Vec<String> = (0..FEATURE_BIN_COUNT).map(|i| format!("mag_bin_{}", i)).collect();
+        writeln!(
+            features_file,
+            "channel,spectral_centroid_hz,dominant_frequency_hz,band_energy,{}",
+            bin_headers.join(",")
+        ).map_err(|e| format!("Failed to write features header: {}", e))?;
+
+        for channel in 0..5 {
+            let series: Vec<f64> = normalized_series.iter().map(|s| s[channel]).collect();
+            let features = compute_channel_features(&series, sample_rate_hz);
+
+            let bins: Vec<String> = features.magnitude_bins.iter().map(|b| format!("{:.6}", b)).collect();
+            writeln!(
+                features_file,
+                "{},{:.6},{:.6},{:.6},{}",
+                channel, features.spectral_centroid_hz, features.dominant_frequency_hz, features.band_energy, bins.join(",")
+            ).map_err(|e| format!("Failed to write features row: {}", e))?;
+        }
     }
 
     Ok(filepath.to_string_lossy().to_string())
@@ -378,18 +1212,181 @@ fn save_recording(
 
 fn main() {
     tauri::Builder::default()
-        .manage(SerialState {
-            port: Arc::new(Mutex::new(None)),
+        .manage(StreamState {
+            serial_port: Arc::new(Mutex::new(None)),
+            tcp_stream: Arc::new(Mutex::new(None)),
+            ws_task: Arc::new(Mutex::new(None)),
+            active: Arc::new(Mutex::new(ActiveTransport::None)),
             is_connected: Arc::new(Mutex::new(false)),
         })
+        .manage(SensorServerState {
+            clients: Arc::new(Mutex::new(Vec::new())),
+            listening: Arc::new(Mutex::new(false)),
+        })
+        .manage(TtsState {
+            engine: Arc::new(Mutex::new(
+                Tts::default().expect("failed to initialize TTS engine"),
+            )),
+        })
+        .manage(RecognitionState {
+            config: Arc::new(Mutex::new(RecognitionConfig::default())),
+        })
+        .manage(CalibrationState {
+            capture: Arc::new(Mutex::new(None)),
+            data: Arc::new(Mutex::new(CalibrationData::default())),
+        })
+        .setup(|app| {
+            if let Some(data) = load_calibration_from_disk(&app.handle()) {
+                let state = app.state::<CalibrationState>();
+                *state.data.lock().unwrap() = data;
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             tts_say,
+            tts_stop,
+            list_voices,
             list_ports,
             connect_serial,
+            connect_network,
             disconnect_serial,
             is_serial_connected,
-            save_recording
+            start_sensor_server,
+            save_recording,
+            set_recognition_config,
+            start_calibration,
+            finish_calibration
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_of(sample: [f64; 5], len: usize) -> VecDeque<[f64; 5]> {
+        let mut window = VecDeque::new();
+        for _ in 0..len {
+            window.push_back(sample);
+        }
+        window
+    }
+
+    #[test]
+    fn motion_energy_is_zero_for_a_held_pose() {
+        // A fist held perfectly still has no inter-sample movement, which is
+        // exactly the case that broke the old `last_finalized` dedup: low
+        // motion energy does not imply the hand returned to rest.
+        let window = window_of([1.0, 1.0, 1.0, 1.0, 1.0], 10);
+        assert_eq!(motion_energy(&window), 0.0);
+    }
+
+    #[test]
+    fn motion_energy_is_positive_when_channels_change() {
+        let mut window = window_of([0.0, 0.0, 0.0, 0.0, 0.0], 5);
+        window.push_back([1.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!(motion_energy(&window) > 0.0);
+    }
+
+    #[test]
+    fn classify_window_matches_fist_template() {
+        let window = window_of([1.0, 1.0, 1.0, 1.0, 1.0], 5);
+        let (label, confidence) = classify_window(&window);
+        assert_eq!(label, "fist");
+        assert_eq!(confidence, 1.0);
+    }
+
+    #[test]
+    fn held_gesture_only_finalizes_once_until_rest() {
+        let config = RecognitionConfig::default();
+        let mut recognizer = GestureRecognizer::new();
+
+        // Build up stability on "fist" until it finalizes.
+        let mut finalized_count = 0;
+        for _ in 0..config.stable_windows_required {
+            if recognizer.step("fist", 1.0, 0.0, config).is_some() {
+                finalized_count += 1;
+            }
+        }
+        assert_eq!(finalized_count, 1, "gesture should finalize exactly once when it first stabilizes");
+
+        // The hand keeps holding the fist: motion energy stays ~0 every
+        // subsequent window, which used to re-clear `last_finalized` and
+        // re-finalize (and re-speak) forever.
+        for _ in 0..20 {
+            assert_eq!(
+                recognizer.step("fist", 1.0, 0.0, config),
+                None,
+                "a held gesture must not re-finalize before the hand returns to rest"
+            );
+        }
+
+        // Only once the window actually classifies as rest does the dedup
+        // re-arm, allowing the same gesture to finalize again later.
+        assert_eq!(recognizer.step("rest", 1.0, 0.0, config), None);
+        let mut finalized_count = 0;
+        for _ in 0..config.stable_windows_required {
+            if recognizer.step("fist", 1.0, 0.0, config).is_some() {
+                finalized_count += 1;
+            }
+        }
+        assert_eq!(finalized_count, 1);
+    }
+
+    #[test]
+    fn next_pow2_rounds_up_to_the_nearest_power_of_two() {
+        assert_eq!(next_pow2(1), 1);
+        assert_eq!(next_pow2(2), 2);
+        assert_eq!(next_pow2(3), 4);
+        assert_eq!(next_pow2(128), 128);
+        assert_eq!(next_pow2(129), 256);
+    }
+
+    #[test]
+    fn estimate_sample_rate_hz_recovers_a_known_rate() {
+        // 10ms between samples, i.e. 100Hz, with one jittery gap that the
+        // median should shrug off.
+        let timestamps: Vec<i64> = vec![0, 10, 20, 30, 55, 60, 70];
+        let rate = estimate_sample_rate_hz(&timestamps);
+        assert!((rate - 100.0).abs() < 1e-9, "expected ~100Hz, got {}", rate);
+    }
+
+    #[test]
+    fn estimate_sample_rate_hz_falls_back_for_too_few_samples() {
+        assert_eq!(estimate_sample_rate_hz(&[]), 1.0);
+        assert_eq!(estimate_sample_rate_hz(&[42]), 1.0);
+    }
+
+    #[test]
+    fn compute_channel_features_recovers_dominant_frequency() {
+        // A 10Hz sine sampled at 100Hz for 128 samples (~1.28s).
+        let sample_rate_hz = 100.0;
+        let signal_hz = 10.0;
+        let series: Vec<f64> = (0..128)
+            .map(|i| (2.0 * std::f64::consts::PI * signal_hz * i as f64 / sample_rate_hz).sin())
+            .collect();
+
+        let features = compute_channel_features(&series, sample_rate_hz);
+
+        assert!(
+            (features.dominant_frequency_hz - signal_hz).abs() < 1.0,
+            "expected dominant frequency near {}Hz, got {}Hz",
+            signal_hz,
+            features.dominant_frequency_hz
+        );
+        assert!(features.band_energy > 0.0);
+        assert_eq!(features.magnitude_bins.len(), FEATURE_BIN_COUNT);
+    }
+
+    #[test]
+    fn channel_median_picks_the_middle_value_per_channel() {
+        let mut window: VecDeque<[i32; 5]> = VecDeque::new();
+        window.push_back([10, 100, 0, 0, 0]);
+        window.push_back([30, 100, 0, 0, 0]);
+        window.push_back([20, 100, 0, 0, 0]);
+
+        assert_eq!(channel_median(&window, 0), 20);
+        assert_eq!(channel_median(&window, 1), 100);
+    }
+}